@@ -0,0 +1,37 @@
+mod onnx;
+pub mod registry;
+mod rest;
+mod retry;
+
+pub use onnx::OnnxEmbedder;
+pub use registry::{find_model, ModelDescriptor, DEFAULT_MODEL, MODEL_REGISTRY};
+pub use rest::{RestEmbedder, DEFAULT_MODEL_NAME as REST_DEFAULT_MODEL_NAME};
+
+use crate::error::EmbedError;
+use rayon::ThreadPool;
+
+/// Default task when `EmbedRequest.task` is omitted.
+pub const DEFAULT_TASK: &str = "document";
+
+/// Common interface for text-embedding backends.
+///
+/// Implemented by the local ONNX-based `OnnxEmbedder` and by `RestEmbedder`,
+/// which proxies to an external OpenAI-compatible `/embeddings` endpoint.
+/// `function_handler` calls through this trait object, chosen at cold start,
+/// so it doesn't need to know which backend is active.
+pub trait Embedder: Send {
+    /// Generates an embedding vector for the given text.
+    fn embed(&mut self, text: &str, size: usize, task: &str) -> Result<Vec<f32>, EmbedError>;
+
+    /// Generates embedding vectors for a batch of texts, in the same order as `texts`.
+    fn embed_batch(
+        &mut self,
+        texts: &[String],
+        size: usize,
+        task: &str,
+        pool: &ThreadPool,
+    ) -> Result<Vec<Vec<f32>>, EmbedError>;
+
+    /// The output dimension this backend produces.
+    fn dimensions(&self) -> usize;
+}
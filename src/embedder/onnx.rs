@@ -0,0 +1,411 @@
+use super::registry::ModelDescriptor;
+use crate::error::EmbedError;
+use ort::{session::Session, value::Value};
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use tokenizers::Tokenizer;
+
+/// Maximum number of texts packed into a single ONNX inference call.
+/// Bounds the memory used by the padded `[batch, max_len, hidden]` output
+/// tensor; larger batches are split into chunks of this size.
+const MAX_BATCH_SIZE: usize = 32;
+
+/// Prompt templates per task, selected via `EmbedRequest.task`.
+/// EmbeddingGemma produces meaningfully different vectors depending on the
+/// task the text is used for; each template's `{text}` placeholder is
+/// substituted in at tokenization time.
+const TASK_TEMPLATES: &[(&str, &str)] = &[
+    ("query", "task: search result | query: {text}"),
+    ("document", "title: none | text: {text}"),
+    ("classification", "task: classification | text: {text}"),
+    ("similarity", "task: sentence similarity | text: {text}"),
+];
+
+/// Handles text embedding using ONNX Runtime.
+///
+/// The local embedding backend: loads an ONNX model and tokenizer, then
+/// provides a simple interface to convert text into vector embeddings.
+/// Implements the `Embedder` trait alongside `super::rest::RestEmbedder`.
+pub struct OnnxEmbedder {
+    session: Session,
+    tokenizer: Tokenizer,
+    valid_dimensions: &'static [usize],
+    max_sequence_length: usize,
+}
+
+impl OnnxEmbedder {
+    /// Creates a new Embedder instance for a registered model.
+    ///
+    /// # Note
+    /// The ONNX model uses external data storage. Both `model_quantized.onnx` and
+    /// `model_quantized.onnx_data` must be present in the same directory.
+    /// ONNX Runtime automatically loads the external data file.
+    pub fn new(descriptor: &ModelDescriptor) -> Result<Self, EmbedError> {
+        // Initialize ONNX Runtime session with optimization level Basic (Level 1)
+        // This enables standard graph optimizations for better performance on ARM64.
+        let session = Session::builder()?
+            .with_optimization_level(ort::session::builder::GraphOptimizationLevel::Level1)?
+            .with_intra_threads(1)? // Optimal for Q4 model: single thread reduces overhead
+            .commit_from_file(descriptor.model_path)?;
+
+        // Load the Hugging Face tokenizer from JSON
+        let tokenizer = Tokenizer::from_file(descriptor.tokenizer_path)
+            .map_err(|e| EmbedError::TokenizerLoad {
+                path: descriptor.tokenizer_path.to_string(),
+                reason: e.to_string(),
+            })?;
+
+        Ok(Self {
+            session,
+            tokenizer,
+            valid_dimensions: descriptor.valid_dimensions,
+            max_sequence_length: descriptor.max_sequence_length,
+        })
+    }
+
+    /// Looks up the prompt template for a task name.
+    fn prompt_template(task: &str) -> Result<&'static str, EmbedError> {
+        TASK_TEMPLATES
+            .iter()
+            .find(|(name, _)| *name == task)
+            .map(|(_, template)| *template)
+            .ok_or_else(|| EmbedError::InvalidTask {
+                task: task.to_string(),
+                valid: TASK_TEMPLATES.iter().map(|(name, _)| name.to_string()).collect(),
+            })
+    }
+
+    /// Tokenizes input text with the prompt template for `task`.
+    ///
+    /// Takes `tokenizer` explicitly (rather than `&self`) so callers can
+    /// share just the `Tokenizer` across threads, e.g. from inside
+    /// `embed_chunk`'s `pool.install`, without requiring the whole
+    /// `OnnxEmbedder` -- and its `ort::Session` -- to be `Sync`.
+    ///
+    /// EmbeddingGemma expects a task-specific prompt template, e.g. the
+    /// document template "title: none | text: {text}".
+    fn tokenize(
+        tokenizer: &Tokenizer,
+        text: &str,
+        task: &str,
+    ) -> Result<(Vec<i64>, Vec<i64>), EmbedError> {
+        // Apply the prompt template for the requested task
+        let template = Self::prompt_template(task)?;
+        let formatted = template.replace("{text}", text);
+
+        // Tokenize with special tokens (e.g., [CLS], [SEP])
+        let encoding = tokenizer
+            .encode(formatted, true)
+            .map_err(|e| EmbedError::Tokenization(e.to_string()))?;
+
+        // Convert to i64 as required by ONNX Runtime
+        let input_ids: Vec<i64> = encoding.get_ids().iter().map(|&id| id as i64).collect();
+        let attention_mask: Vec<i64> = encoding
+            .get_attention_mask()
+            .iter()
+            .map(|&m| m as i64)
+            .collect();
+
+        Ok((input_ids, attention_mask))
+    }
+
+    /// Returns the tokenizer's pad token id, used to right-pad batched
+    /// sequences up to the batch's `max_len`.
+    fn pad_token_id(&self) -> i64 {
+        self.tokenizer
+            .get_padding()
+            .map(|p| p.pad_id)
+            .or_else(|| self.tokenizer.token_to_id("<pad>"))
+            .unwrap_or(0) as i64
+    }
+
+    /// Right-pads `values` to `max_len` with `pad_value`.
+    fn pad_to(values: &[i64], pad_value: i64, max_len: usize) -> Vec<i64> {
+        let mut padded = Vec::with_capacity(max_len);
+        padded.extend_from_slice(values);
+        padded.resize(max_len, pad_value);
+        padded
+    }
+
+    /// Embeds a single chunk (at most `MAX_BATCH_SIZE` texts) in one
+    /// inference call.
+    ///
+    /// Tokenization of the chunk's texts runs on `pool` since it's CPU-bound
+    /// and independent per text. Only `&self.tokenizer` is shared across
+    /// worker threads for this, not `self` as a whole -- the ONNX session
+    /// only accepts one inference call at a time, so padding and
+    /// `session.run` stay on the calling thread.
+    fn embed_chunk(
+        &mut self,
+        chunk: &[String],
+        size: usize,
+        task: &str,
+        pool: &ThreadPool,
+    ) -> Result<Vec<Vec<f32>>, EmbedError> {
+        // Step 1: Tokenize every text in the chunk, in parallel
+        let tokenizer = &self.tokenizer;
+        let tokenized: Vec<(Vec<i64>, Vec<i64>)> = pool.install(|| {
+            chunk
+                .par_iter()
+                .map(|text| Self::tokenize(tokenizer, text, task))
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+
+        let batch_size = tokenized.len();
+        let max_len = tokenized.iter().map(|(ids, _)| ids.len()).max().unwrap_or(0);
+
+        // Validate the largest padded sequence still respects the limit
+        if max_len > self.max_sequence_length {
+            return Err(EmbedError::SequenceTooLong {
+                got: max_len,
+                max: self.max_sequence_length,
+            });
+        }
+
+        // Step 2: Right-pad every sequence to max_len and stack into
+        // [batch_size, max_len] tensors. Each item's own padded mask is kept
+        // alongside (not just the tensor's flat copy) since pooling needs it
+        // per-row and it must match max_len, not the item's original length.
+        let pad_id = self.pad_token_id();
+        let mut input_ids = Vec::with_capacity(batch_size * max_len);
+        let mut attention_mask = Vec::with_capacity(batch_size * max_len);
+        let mut padded_masks: Vec<Vec<i64>> = Vec::with_capacity(batch_size);
+        for (ids, mask) in &tokenized {
+            input_ids.extend(Self::pad_to(ids, pad_id, max_len));
+            attention_mask.extend(Self::pad_to(mask, 0, max_len));
+            padded_masks.push(Self::pad_to(mask, 0, max_len));
+        }
+
+        // Step 3: Run one inference for the whole chunk
+        let shape = vec![batch_size, max_len];
+        let outputs = self.session.run(ort::inputs![
+            "input_ids" => Value::from_array((shape.clone(), input_ids))?,
+            "attention_mask" => Value::from_array((shape, attention_mask))?,
+        ])?;
+
+        // Step 4: Extract [batch_size, max_len, hidden_dim] and pool each row
+        // independently using its own mask
+        let (output_shape, output_data) = outputs[0].try_extract_tensor::<f32>()?;
+        let hidden_dim = output_shape[2] as usize;
+        let output_view =
+            ndarray::ArrayView3::from_shape((batch_size, max_len, hidden_dim), output_data)?;
+
+        let mut results = Vec::with_capacity(batch_size);
+        for (row, mask) in output_view.outer_iter().zip(&padded_masks) {
+            let row = row.insert_axis(ndarray::Axis(0));
+            let pooled = Self::mean_pooling(&row, mask)?;
+            let truncated: Vec<f32> = pooled.into_iter().take(size).collect();
+            results.push(Self::normalize(&truncated));
+        }
+        Ok(results)
+    }
+
+    /// Applies mean pooling to token embeddings.
+    ///
+    /// Mean pooling averages the embeddings of all non-padding tokens.
+    /// The attention mask is used to exclude padding tokens from the average.
+    /// Uses vectorized ndarray operations for optimal performance.
+    fn mean_pooling(
+        hidden_states: &ndarray::ArrayView3<f32>,
+        attention_mask: &[i64],
+    ) -> Result<Vec<f32>, EmbedError> {
+        use ndarray::Axis;
+
+        // hidden_states: [batch=1, seq_len, hidden_dim]
+        // Remove batch dimension: [seq_len, hidden_dim]
+        let states_2d = hidden_states.index_axis(Axis(0), 0);
+
+        // Convert mask to f32 and create array
+        let mask_f32: Vec<f32> = attention_mask.iter().map(|&x| x as f32).collect();
+        let mask_1d = ndarray::Array1::from(mask_f32);
+
+        // Count non-padding tokens (do this before consuming mask_1d)
+        let count = mask_1d.sum();
+
+        // Reshape to [seq_len, 1] for broadcasting
+        let mask_col = mask_1d.insert_axis(Axis(1)); // Shape: [seq_len, 1]
+
+        // Broadcast multiply: each token embedding is scaled by its mask value
+        // This zeros out padding tokens
+        let masked_states = &states_2d * &mask_col;
+
+        // Sum along sequence axis: [seq_len, hidden_dim] -> [hidden_dim]
+        let sum = masked_states.sum_axis(Axis(0));
+
+        // Compute mean (avoid division by zero)
+        let mean = if count > 0.0 { sum / count } else { sum };
+
+        Ok(mean.to_vec())
+    }
+
+    /// Applies L2 normalization to the embedding vector.
+    ///
+    /// Normalized embeddings allow using dot product instead of cosine similarity,
+    /// which is computationally cheaper for similarity searches.
+    fn normalize(embedding: &[f32]) -> Vec<f32> {
+        let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+        if norm > 0.0 {
+            embedding.iter().map(|x| x / norm).collect()
+        } else {
+            embedding.to_vec()
+        }
+    }
+}
+
+impl super::Embedder for OnnxEmbedder {
+    /// Generates an embedding vector for the given text.
+    ///
+    /// # Arguments
+    /// * `text` - The input text to embed
+    /// * `size` - Output dimension: 768, 512, 256, or 128 (Matryoshka truncation)
+    /// * `task` - Prompt template to apply, e.g. "query", "document", "classification", "similarity"
+    ///
+    /// # Returns
+    /// A normalized embedding vector of the requested dimension
+    fn embed(&mut self, text: &str, size: usize, task: &str) -> Result<Vec<f32>, EmbedError> {
+        // Validate the requested dimension
+        if !self.valid_dimensions.contains(&size) {
+            return Err(EmbedError::InvalidDimension {
+                size,
+                valid: self.valid_dimensions.to_vec(),
+            });
+        }
+
+        // Step 1: Tokenize the input
+        let (input_ids, attention_mask) = Self::tokenize(&self.tokenizer, text, task)?;
+        let seq_len = input_ids.len();
+
+        // Validate sequence length
+        if seq_len > self.max_sequence_length {
+            return Err(EmbedError::SequenceTooLong {
+                got: seq_len,
+                max: self.max_sequence_length,
+            });
+        }
+
+        // Step 2: Prepare inputs as 2D tensors with shape [batch_size=1, seq_len]
+        let shape = vec![1, seq_len];
+
+        // Step 3: Run inference
+        let outputs = self.session.run(ort::inputs![
+            "input_ids" => Value::from_array((shape.clone(), input_ids))?,
+            "attention_mask" => Value::from_array((shape, attention_mask.clone()))?,
+        ])?;
+
+        // Step 4: Extract the output tensor
+        // The model outputs last_hidden_state with shape [batch_size, seq_len, hidden_dim]
+        let (output_shape, output_data) = outputs[0].try_extract_tensor::<f32>()?;
+        let batch_size = output_shape[0] as usize;
+        let seq_len_out = output_shape[1] as usize;
+        let hidden_dim = output_shape[2] as usize;
+
+        // Convert to ArrayView3 for mean_pooling
+        let output_view =
+            ndarray::ArrayView3::from_shape((batch_size, seq_len_out, hidden_dim), output_data)?;
+
+        // Step 5: Apply mean pooling over token embeddings
+        let embedding = Self::mean_pooling(&output_view, &attention_mask)?;
+
+        // Step 6: Truncate to requested dimension (Matryoshka)
+        let truncated: Vec<f32> = embedding.into_iter().take(size).collect();
+
+        // Step 7: L2 normalize the final embedding
+        // Re-normalization after truncation is important for correct similarity scores
+        let normalized = Self::normalize(&truncated);
+
+        Ok(normalized)
+    }
+
+    /// Generates embedding vectors for a batch of texts in as few inference
+    /// calls as possible.
+    ///
+    /// # Arguments
+    /// * `texts` - The input texts to embed
+    /// * `size` - Output dimension: 768, 512, 256, or 128 (Matryoshka truncation)
+    /// * `task` - Prompt template to apply, e.g. "query", "document", "classification", "similarity"
+    /// * `pool` - Thread pool used to tokenize each chunk's texts in parallel
+    ///
+    /// # Returns
+    /// Normalized embedding vectors, in the same order as `texts`
+    fn embed_batch(
+        &mut self,
+        texts: &[String],
+        size: usize,
+        task: &str,
+        pool: &ThreadPool,
+    ) -> Result<Vec<Vec<f32>>, EmbedError> {
+        // Validate the requested dimension
+        if !self.valid_dimensions.contains(&size) {
+            return Err(EmbedError::InvalidDimension {
+                size,
+                valid: self.valid_dimensions.to_vec(),
+            });
+        }
+
+        if texts.is_empty() {
+            return Err(EmbedError::EmptyInput);
+        }
+
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for chunk in texts.chunks(MAX_BATCH_SIZE) {
+            embeddings.extend(self.embed_chunk(chunk, size, task, pool)?);
+        }
+        Ok(embeddings)
+    }
+
+    /// The native output dimension of the loaded model (the largest
+    /// Matryoshka truncation size).
+    fn dimensions(&self) -> usize {
+        self.valid_dimensions[0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{ArrayView3, Axis};
+
+    #[test]
+    fn pad_to_right_pads_with_given_value() {
+        assert_eq!(OnnxEmbedder::pad_to(&[1, 2], 0, 4), vec![1, 2, 0, 0]);
+        assert_eq!(OnnxEmbedder::pad_to(&[1, 2, 3], 9, 3), vec![1, 2, 3]);
+    }
+
+    /// Regression test for the panic fixed in aba6df4: `embed_chunk` pools
+    /// each batch row against a mask padded to the batch's max_len, not the
+    /// item's own (shorter) original length. Build a batch of two texts
+    /// with different real lengths, padded to a shared max_len, and check
+    /// pooling doesn't panic and excludes the padding from the average.
+    #[test]
+    fn mean_pooling_handles_mixed_length_batch_without_panicking() {
+        let hidden_dim = 2;
+        let max_len = 3;
+
+        // Row 0: 2 real tokens ([1,1], [3,3]) + 1 pad token ([9,9], masked out)
+        let row0: Vec<f32> = vec![1.0, 1.0, 3.0, 3.0, 9.0, 9.0];
+        let mask0 = OnnxEmbedder::pad_to(&[1, 1], 0, max_len);
+
+        // Row 1: 3 real tokens, no padding needed
+        let row1: Vec<f32> = vec![2.0, 2.0, 4.0, 4.0, 6.0, 6.0];
+        let mask1 = OnnxEmbedder::pad_to(&[1, 1, 1], 0, max_len);
+
+        let batch_data: Vec<f32> = [row0, row1].concat();
+        let batch_view: ArrayView3<f32> =
+            ndarray::ArrayView3::from_shape((2, max_len, hidden_dim), &batch_data).unwrap();
+
+        let pooled0 = OnnxEmbedder::mean_pooling(
+            &batch_view.index_axis(Axis(0), 0).insert_axis(Axis(0)),
+            &mask0,
+        )
+        .unwrap();
+        assert_eq!(pooled0, vec![2.0, 2.0]); // average of [1,1] and [3,3], pad excluded
+
+        let pooled1 = OnnxEmbedder::mean_pooling(
+            &batch_view.index_axis(Axis(0), 1).insert_axis(Axis(0)),
+            &mask1,
+        )
+        .unwrap();
+        assert_eq!(pooled1, vec![4.0, 4.0]); // average of [2,2], [4,4], [6,6]
+    }
+}
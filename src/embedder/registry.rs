@@ -0,0 +1,40 @@
+/// Describes one embedding model the service can load.
+///
+/// Mirrors how MeiliSearch's `EmbeddingModel` enum carries each model's
+/// supported dimensions and max token count, except here it's data rather
+/// than enum variants, so adding a model is a registry entry, not a
+/// recompile of the match arms.
+pub struct ModelDescriptor {
+    /// Name clients select via `EmbedRequest.model`
+    pub name: &'static str,
+    pub model_path: &'static str,
+    pub tokenizer_path: &'static str,
+    /// Valid Matryoshka truncation sizes for this model, largest (native) first
+    pub valid_dimensions: &'static [usize],
+    /// Max sequence length in tokens, used in the `SequenceTooLong` check
+    pub max_sequence_length: usize,
+}
+
+impl ModelDescriptor {
+    /// The model's native output dimension (no truncation)
+    pub fn native_dimension(&self) -> usize {
+        self.valid_dimensions[0]
+    }
+}
+
+/// All embedding models this service can load, keyed by `name`.
+pub const MODEL_REGISTRY: &[ModelDescriptor] = &[ModelDescriptor {
+    name: "embeddinggemma-300m",
+    model_path: "model/model_quantized.onnx",
+    tokenizer_path: "model/tokenizer.json",
+    valid_dimensions: &[768, 512, 256, 128],
+    max_sequence_length: 8192,
+}];
+
+/// Default model when `EmbedRequest.model` is omitted.
+pub const DEFAULT_MODEL: &str = "embeddinggemma-300m";
+
+/// Looks up a model descriptor by name.
+pub fn find_model(name: &str) -> Option<&'static ModelDescriptor> {
+    MODEL_REGISTRY.iter().find(|m| m.name == name)
+}
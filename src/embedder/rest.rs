@@ -0,0 +1,161 @@
+use super::retry::Retry;
+use crate::error::EmbedError;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use serde_json::Value;
+use ureq::Agent;
+
+/// Model key the REST backend is registered under when the operator
+/// doesn't set `REST_EMBEDDER_MODEL_NAME`. Distinct from the ONNX
+/// registry's `DEFAULT_MODEL` -- the REST backend proxies to whatever
+/// model `REST_EMBEDDER_URL` points at, which has no relation to the
+/// bundled ONNX model of the same default name.
+pub const DEFAULT_MODEL_NAME: &str = "rest";
+
+/// Embedding backend that proxies to an external OpenAI-compatible
+/// `/embeddings` endpoint.
+///
+/// Lets this Lambda serve as a thin proxy/fallback when the bundled ONNX
+/// model can't be shipped (e.g. package size limits). Implements the same
+/// `Embedder` trait as `OnnxEmbedder` so callers dispatch through a trait
+/// object without caring which backend is active.
+pub struct RestEmbedder {
+    agent: Agent,
+    url: String,
+    bearer_token: Option<String>,
+    /// JSON field the input text is written to in the request body
+    request_field: String,
+    /// Dotted JSON path to the embedding array in the response body,
+    /// e.g. "data.0.embedding" for an OpenAI-shaped response
+    response_field: String,
+    dimensions: usize,
+}
+
+impl RestEmbedder {
+    /// Creates a new RestEmbedder.
+    ///
+    /// # Arguments
+    /// * `url` - Full URL of the remote `/embeddings` endpoint
+    /// * `bearer_token` - Optional `Authorization: Bearer <token>` credential
+    /// * `request_field` - JSON field the input text is written to (default: "input")
+    /// * `response_field` - Dotted JSON path to the embedding array in the response (default: "data.0.embedding")
+    /// * `dimensions` - Output dimension; if `None`, inferred from one probe request
+    pub fn new(
+        url: String,
+        bearer_token: Option<String>,
+        request_field: Option<String>,
+        response_field: Option<String>,
+        dimensions: Option<usize>,
+    ) -> Result<Self, EmbedError> {
+        let mut embedder = Self {
+            agent: Agent::new(),
+            url,
+            bearer_token,
+            request_field: request_field.unwrap_or_else(|| "input".to_string()),
+            response_field: response_field.unwrap_or_else(|| "data.0.embedding".to_string()),
+            dimensions: dimensions.unwrap_or(0),
+        };
+
+        if let Some(dimensions) = dimensions {
+            embedder.dimensions = dimensions;
+        } else {
+            let probe = embedder.request_embedding("probe", super::DEFAULT_TASK)?;
+            embedder.dimensions = probe.len();
+        }
+
+        Ok(embedder)
+    }
+
+    /// Sends one text to the remote endpoint and returns its embedding.
+    ///
+    /// The HTTP call itself is wrapped in `Retry::run` so a transient
+    /// provider hiccup (5xx, rate limit, transport error) doesn't surface
+    /// as a failure on the first attempt.
+    ///
+    /// `task` is accepted for interface parity with `OnnxEmbedder::embed`
+    /// but isn't sent on the wire: a real OpenAI-compatible `/embeddings`
+    /// endpoint rejects unrecognized top-level request fields, so there's
+    /// no generic, safe way to forward it.
+    fn request_embedding(&self, text: &str, _task: &str) -> Result<Vec<f32>, EmbedError> {
+        let mut body = serde_json::Map::new();
+        body.insert(self.request_field.clone(), Value::String(text.to_string()));
+        let payload = Value::Object(body);
+
+        let response = Retry::run(|| {
+            let mut req = self.agent.post(&self.url).set("content-type", "application/json");
+            if let Some(token) = &self.bearer_token {
+                req = req.set("authorization", &format!("Bearer {token}"));
+            }
+            req.send_json(payload.clone())
+        })?;
+
+        let json: Value = response
+            .into_json()
+            .map_err(|e| EmbedError::Internal(format!("Invalid REST embedding response: {e}")))?;
+
+        Self::extract_field(&json, &self.response_field)
+            .and_then(Value::as_array)
+            .map(|values| values.iter().map(|v| v.as_f64().unwrap_or(0.0) as f32).collect())
+            .ok_or_else(|| {
+                EmbedError::Internal(format!(
+                    "REST embedding response missing field `{}`",
+                    self.response_field
+                ))
+            })
+    }
+
+    /// Walks a dotted JSON path, treating numeric segments as array indices
+    /// (e.g. "data.0.embedding").
+    fn extract_field<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+        path.split('.').try_fold(value, |current, segment| {
+            if let Ok(index) = segment.parse::<usize>() {
+                current.get(index)
+            } else {
+                current.get(segment)
+            }
+        })
+    }
+}
+
+impl super::Embedder for RestEmbedder {
+    fn embed(&mut self, text: &str, size: usize, task: &str) -> Result<Vec<f32>, EmbedError> {
+        if size != self.dimensions {
+            return Err(EmbedError::InvalidDimension {
+                size,
+                valid: vec![self.dimensions],
+            });
+        }
+
+        self.request_embedding(text, task)
+    }
+
+    fn embed_batch(
+        &mut self,
+        texts: &[String],
+        size: usize,
+        task: &str,
+        pool: &ThreadPool,
+    ) -> Result<Vec<Vec<f32>>, EmbedError> {
+        if size != self.dimensions {
+            return Err(EmbedError::InvalidDimension {
+                size,
+                valid: vec![self.dimensions],
+            });
+        }
+
+        if texts.is_empty() {
+            return Err(EmbedError::EmptyInput);
+        }
+
+        pool.install(|| {
+            texts
+                .par_iter()
+                .map(|text| self.request_embedding(text, task))
+                .collect()
+        })
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+}
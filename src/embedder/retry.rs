@@ -0,0 +1,72 @@
+use crate::error::EmbedError;
+use std::time::Duration;
+
+/// How to proceed after a failed remote embedding call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryStrategy {
+    /// Stop retrying immediately; the failure is not transient
+    GiveUp,
+    /// Retry after a generic exponential backoff
+    Retry,
+    /// Retry after a backoff that additionally accounts for a rate limit
+    RetryAfterRateLimit,
+}
+
+/// Wraps a remote embedding call with bounded retries and exponential
+/// backoff, so a transient provider hiccup doesn't surface as a 500.
+pub struct Retry;
+
+impl Retry {
+    /// Maximum number of attempts (including the first) before giving up
+    const MAX_ATTEMPTS: u32 = 5;
+
+    /// Runs `call`, retrying transient failures (per `classify`) with
+    /// backoff up to `MAX_ATTEMPTS` attempts. A non-retryable failure, or
+    /// exhausting all attempts, is converted to an `EmbedError`.
+    pub fn run<T>(mut call: impl FnMut() -> Result<T, ureq::Error>) -> Result<T, EmbedError> {
+        let mut attempt = 0;
+        loop {
+            match call() {
+                Ok(value) => return Ok(value),
+                Err(error) => {
+                    let strategy = Self::classify(&error);
+                    attempt += 1;
+                    if strategy == RetryStrategy::GiveUp || attempt >= Self::MAX_ATTEMPTS {
+                        return Err(Self::to_embed_error(error));
+                    }
+                    std::thread::sleep(Self::into_duration(attempt, strategy));
+                }
+            }
+        }
+    }
+
+    /// Classifies a failed HTTP call: 401 can't be fixed by retrying, 429
+    /// needs the rate-limit backoff, 5xx and transport errors are
+    /// transient, and anything else isn't worth retrying.
+    fn classify(error: &ureq::Error) -> RetryStrategy {
+        match error {
+            ureq::Error::Status(401, _) => RetryStrategy::GiveUp,
+            ureq::Error::Status(429, _) => RetryStrategy::RetryAfterRateLimit,
+            ureq::Error::Status(status, _) if *status >= 500 => RetryStrategy::Retry,
+            ureq::Error::Status(_, _) => RetryStrategy::GiveUp,
+            ureq::Error::Transport(_) => RetryStrategy::Retry,
+        }
+    }
+
+    /// Computes how long to sleep before the given attempt number.
+    fn into_duration(attempt: u32, strategy: RetryStrategy) -> Duration {
+        let millis = match strategy {
+            RetryStrategy::RetryAfterRateLimit => 100 + 10u64.saturating_pow(attempt),
+            _ => 10u64.saturating_pow(attempt),
+        };
+        Duration::from_millis(millis)
+    }
+
+    fn to_embed_error(error: ureq::Error) -> EmbedError {
+        if let ureq::Error::Status(401, _) = error {
+            EmbedError::Unauthorized(error.to_string())
+        } else {
+            EmbedError::Internal(format!("REST embedding request failed: {error}"))
+        }
+    }
+}
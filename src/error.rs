@@ -10,6 +10,20 @@ pub enum EmbedError {
         valid: Vec<usize>,
     },
 
+    /// Unknown task name requested
+    #[error("Invalid task: {task}. Must be one of: {valid:?}")]
+    InvalidTask {
+        task: String,
+        valid: Vec<String>,
+    },
+
+    /// Unknown model name requested
+    #[error("Invalid model: {model}. Must be one of: {valid:?}")]
+    InvalidModel {
+        model: String,
+        valid: Vec<String>,
+    },
+
     /// Input sequence is too long
     #[error("Tokenized sequence exceeds maximum length of {max} tokens (got {got})")]
     SequenceTooLong {
@@ -28,6 +42,13 @@ pub enum EmbedError {
         max: usize,
     },
 
+    /// Batch request has too many texts
+    #[error("Batch exceeds maximum of {max} texts (got {got})")]
+    BatchTooLarge {
+        got: usize,
+        max: usize,
+    },
+
     /// Failed to load tokenizer
     #[error("Failed to load tokenizer from {path}: {reason}")]
     TokenizerLoad {
@@ -51,6 +72,11 @@ pub enum EmbedError {
     #[error("Internal error: shared resource poisoned")]
     MutexPoisoned,
 
+    /// A remote embedding backend rejected our credentials; retrying
+    /// wouldn't help, so this is surfaced as a client-facing auth error
+    #[error("Upstream authentication failed: {0}")]
+    Unauthorized(String),
+
     /// Internal server error (catch-all)
     #[error("Internal server error: {0}")]
     Internal(String),
@@ -62,18 +88,22 @@ impl EmbedError {
         matches!(
             self,
             EmbedError::InvalidDimension { .. }
+                | EmbedError::InvalidTask { .. }
+                | EmbedError::InvalidModel { .. }
                 | EmbedError::SequenceTooLong { .. }
                 | EmbedError::EmptyInput
                 | EmbedError::TextTooLong { .. }
+                | EmbedError::BatchTooLarge { .. }
+                | EmbedError::Unauthorized(_)
         )
     }
 
     /// Get the HTTP status code for this error
     pub fn status_code(&self) -> u16 {
-        if self.is_client_error() {
-            400
-        } else {
-            500
+        match self {
+            EmbedError::Unauthorized(_) => 401,
+            _ if self.is_client_error() => 400,
+            _ => 500,
         }
     }
 
@@ -84,6 +114,12 @@ impl EmbedError {
             EmbedError::InvalidDimension { size, valid } => {
                 format!("Invalid embedding size: {}. Must be one of: {:?}", size, valid)
             }
+            EmbedError::InvalidTask { task, valid } => {
+                format!("Invalid task: {}. Must be one of: {:?}", task, valid)
+            }
+            EmbedError::InvalidModel { model, valid } => {
+                format!("Invalid model: {}. Must be one of: {:?}", model, valid)
+            }
             EmbedError::SequenceTooLong { got, max } => {
                 format!("Text is too long: {} tokens (max: {})", got, max)
             }
@@ -91,9 +127,15 @@ impl EmbedError {
             EmbedError::TextTooLong { got, max } => {
                 format!("Text is too long: {} characters (max: {})", got, max)
             }
+            EmbedError::BatchTooLarge { got, max } => {
+                format!("Batch is too large: {} texts (max: {})", got, max)
+            }
             EmbedError::Tokenization(msg) => {
                 format!("Failed to process text: {}", msg)
             }
+            EmbedError::Unauthorized(_) => {
+                "Authentication with the embedding provider failed".to_string()
+            }
 
             // Server errors - generic message in production
             _ => {
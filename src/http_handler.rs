@@ -1,7 +1,9 @@
-use crate::embedder::{Embedder, VALID_DIMENSIONS};
+use crate::embedder::{Embedder, DEFAULT_MODEL, DEFAULT_TASK};
 use crate::error::EmbedError;
 use lambda_http::{Body, Error, Request, Response};
+use rayon::ThreadPool;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tracing::{error, info, warn};
 
@@ -9,21 +11,47 @@ use tracing::{error, info, warn};
 /// Prevents OOM from extremely long inputs
 const MAX_TEXT_LENGTH: usize = 100_000;
 
-/// Incoming request payload
+/// Maximum number of texts accepted in a single `texts` batch request.
+/// Bounds the work one invocation can be asked to do; unlike
+/// `MAX_BATCH_SIZE` in `embedder::onnx`, which only chunks an already
+/// accepted batch for a single inference call, this rejects oversized
+/// requests up front.
+const MAX_BATCH_TEXTS: usize = 256;
+
+/// Incoming request payload.
+///
+/// Exactly one of `text` (single embedding) or `texts` (batch embedding)
+/// must be present.
 #[derive(Deserialize)]
 struct EmbedRequest {
-    /// The text to embed
-    text: String,
+    /// A single text to embed
+    text: Option<String>,
+    /// A batch of texts to embed
+    texts: Option<Vec<String>>,
     /// Output dimension: 768, 512, 256, or 128 (default: 768)
     #[serde(default = "default_size")]
     size: usize,
+    /// Prompt task: "query", "document", "classification", or "similarity" (default: "document")
+    #[serde(default = "default_task")]
+    task: String,
+    /// Embedding model to use (default: "embeddinggemma-300m")
+    #[serde(default = "default_model")]
+    model: String,
 }
 
 fn default_size() -> usize {
     768
 }
 
-/// Response payload containing the embedding vector
+fn default_task() -> String {
+    DEFAULT_TASK.to_string()
+}
+
+fn default_model() -> String {
+    DEFAULT_MODEL.to_string()
+}
+
+/// Response payload containing a single embedding vector
 #[derive(Serialize)]
 struct EmbedResponse {
     /// The embedding vector
@@ -32,6 +60,15 @@ struct EmbedResponse {
     size: usize,
 }
 
+/// Response payload containing a batch of embedding vectors
+#[derive(Serialize)]
+struct BatchEmbedResponse {
+    /// The embedding vectors, in the same order as the request's `texts`
+    embeddings: Vec<Vec<f32>>,
+    /// Dimension of each embedding
+    size: usize,
+}
+
 /// Error response payload
 #[derive(Serialize)]
 struct ErrorResponse {
@@ -43,7 +80,8 @@ struct ErrorResponse {
 /// Receives an HTTP request with JSON body, generates an embedding,
 /// and returns it as a JSON response.
 pub async fn function_handler(
-    embedder: Arc<Mutex<Embedder>>,
+    embedders: Arc<HashMap<String, Arc<Mutex<Box<dyn Embedder>>>>>,
+    pool: Arc<ThreadPool>,
     event: Request,
 ) -> Result<Response<Body>, Error> {
     // Parse the JSON request body
@@ -55,60 +93,115 @@ pub async fn function_handler(
         }
     };
 
-    // Validate the size parameter
-    if !VALID_DIMENSIONS.contains(&request.size) {
-        return Ok(error_response(
-            400,
-            &format!(
-                "Invalid size: {}. Must be one of: {:?}",
-                request.size, VALID_DIMENSIONS
-            ),
-        ));
+    // Look up the requested model among the ones loaded at cold start
+    let embedder = match embedders.get(&request.model) {
+        Some(embedder) => embedder,
+        None => {
+            warn!("Unknown model requested: {}", request.model);
+            let mut valid: Vec<String> = embedders.keys().cloned().collect();
+            valid.sort();
+            return Ok(error_from_embed_error(&EmbedError::InvalidModel {
+                model: request.model,
+                valid,
+            }));
+        }
+    };
+
+    // Exactly one of `text` or `texts` must be present
+    let (texts, is_batch): (Vec<String>, bool) = match (request.text, request.texts) {
+        (Some(_), Some(_)) => {
+            return Ok(error_response(
+                400,
+                "Request must set exactly one of `text` or `texts`, not both",
+            ));
+        }
+        (Some(text), None) => (vec![text], false),
+        (None, Some(texts)) => (texts, true),
+        (None, None) => {
+            return Ok(error_response(400, "Request must set `text` or `texts`"));
+        }
+    };
+
+    if texts.is_empty() {
+        warn!("Empty batch input");
+        return Ok(error_from_embed_error(&EmbedError::EmptyInput));
     }
 
-    // Validate text is not empty
-    if request.text.is_empty() {
-        let err = EmbedError::EmptyInput;
-        warn!("Empty text input");
-        return Ok(error_from_embed_error(&err));
+    if texts.len() > MAX_BATCH_TEXTS {
+        warn!("Batch too large: {} texts", texts.len());
+        return Ok(error_from_embed_error(&EmbedError::BatchTooLarge {
+            got: texts.len(),
+            max: MAX_BATCH_TEXTS,
+        }));
     }
 
-    // Validate text length to prevent OOM
-    if request.text.len() > MAX_TEXT_LENGTH {
-        let err = EmbedError::TextTooLong {
-            got: request.text.len(),
-            max: MAX_TEXT_LENGTH,
-        };
-        warn!("Text too long: {} chars", request.text.len());
-        return Ok(error_from_embed_error(&err));
+    // Validate every text is non-empty and within the character limit
+    for text in &texts {
+        if text.is_empty() {
+            warn!("Empty text input");
+            return Ok(error_from_embed_error(&EmbedError::EmptyInput));
+        }
+        if text.len() > MAX_TEXT_LENGTH {
+            warn!("Text too long: {} chars", text.len());
+            return Ok(error_from_embed_error(&EmbedError::TextTooLong {
+                got: text.len(),
+                max: MAX_TEXT_LENGTH,
+            }));
+        }
     }
 
-    // Generate the embedding
-    // Mutex required: ONNX Runtime Rust bindings need &mut for session.run()
+    // Generate the embedding(s)
+    // Mutex required: the embedder needs &mut to run inference / issue requests
     // Lambda processes one request at a time per container, so no contention
-    let embedding = {
-        // Safe mutex handling - recover from poisoned state
-        let mut embedder = match embedder.lock() {
-            Ok(guard) => guard,
-            Err(poisoned) => {
-                warn!("Mutex was poisoned, recovering...");
-                poisoned.into_inner()
-            }
-        };
+    let mut embedder_guard = match embedder.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            warn!("Mutex was poisoned, recovering...");
+            poisoned.into_inner()
+        }
+    };
 
-        match embedder.embed(&request.text, request.size) {
-            Ok(emb) => {
+    if is_batch {
+        let embeddings = match embedder_guard.embed_batch(&texts, request.size, &request.task, &pool) {
+            Ok(embs) => {
                 info!(
-                    text_len = request.text.len(),
+                    batch_size = texts.len(),
                     embedding_size = request.size,
-                    "Embedding generated successfully"
+                    "Batch embeddings generated successfully"
                 );
-                emb
+                embs
             }
             Err(e) => {
-                error!("Embedding generation failed: {}", e);
+                error!("Batch embedding generation failed: {}", e);
                 return Ok(error_from_embed_error(&e));
             }
+        };
+
+        let response = BatchEmbedResponse {
+            size: request.size,
+            embeddings,
+        };
+        let response_json = serde_json::to_string(&response)?;
+
+        return Ok(Response::builder()
+            .status(200)
+            .header("content-type", "application/json")
+            .body(response_json.into())
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?);
+    }
+
+    let embedding = match embedder_guard.embed(&texts[0], request.size, &request.task) {
+        Ok(emb) => {
+            info!(
+                text_len = texts[0].len(),
+                embedding_size = request.size,
+                "Embedding generated successfully"
+            );
+            emb
+        }
+        Err(e) => {
+            error!("Embedding generation failed: {}", e);
+            return Ok(error_from_embed_error(&e));
         }
     };
 
@@ -2,9 +2,11 @@ pub mod embedder;
 pub mod error;
 pub mod http_handler;
 
-use embedder::Embedder;
+use embedder::{Embedder, OnnxEmbedder, RestEmbedder, MODEL_REGISTRY, REST_DEFAULT_MODEL_NAME};
 use http_handler::function_handler;
 use lambda_http::{run, service_fn, tracing, Error};
+use std::collections::HashMap;
+use std::env;
 use std::sync::{Arc, Mutex};
 
 #[tokio::main]
@@ -17,23 +19,68 @@ async fn main() -> Result<(), Error> {
     // Initialize tracing for CloudWatch logs
     tracing::init_default_subscriber();
 
-    // Initialize the Embedder once during cold start.
-    // This loads the ONNX model and tokenizer into memory.
-    let embedder = Embedder::new("model/model_quantized.onnx", "model/tokenizer.json")
-        .map_err(|e| {
-            tracing::error!("Failed to initialize embedder: {}", e);
-            Box::new(e) as Box<dyn std::error::Error + Send + Sync>
-        })?;
+    // Select the embedding backend once during cold start. Defaults to the
+    // bundled ONNX models in `MODEL_REGISTRY`; set EMBEDDER_BACKEND=rest to
+    // proxy to an external OpenAI-compatible `/embeddings` endpoint instead.
+    let backend = env::var("EMBEDDER_BACKEND").unwrap_or_else(|_| "onnx".to_string());
+    let mut embedders: HashMap<String, Arc<Mutex<Box<dyn Embedder>>>> = HashMap::new();
 
-    // Wrap in Arc<Mutex> to share across handler invocations
-    // Mutex required: ONNX Runtime Rust bindings need &mut for session.run()
-    let embedder = Arc::new(Mutex::new(embedder));
+    match backend.as_str() {
+        "rest" => {
+            let url = env::var("REST_EMBEDDER_URL")
+                .map_err(|_| "REST_EMBEDDER_URL must be set when EMBEDDER_BACKEND=rest")?;
+            let bearer_token = env::var("REST_EMBEDDER_TOKEN").ok();
+            let request_field = env::var("REST_EMBEDDER_REQUEST_FIELD").ok();
+            let response_field = env::var("REST_EMBEDDER_RESPONSE_FIELD").ok();
+            let dimensions = env::var("REST_EMBEDDER_DIMENSIONS")
+                .ok()
+                .and_then(|v| v.parse().ok());
+            // The REST backend proxies to whatever model REST_EMBEDDER_URL
+            // points at, which has no relation to the ONNX registry's model
+            // names, so it gets its own model key rather than borrowing
+            // DEFAULT_MODEL.
+            let model_name = env::var("REST_EMBEDDER_MODEL_NAME")
+                .unwrap_or_else(|_| REST_DEFAULT_MODEL_NAME.to_string());
+
+            let rest_embedder =
+                RestEmbedder::new(url, bearer_token, request_field, response_field, dimensions)
+                    .map_err(|e| {
+                        tracing::error!("Failed to initialize REST embedder: {}", e);
+                        Box::new(e) as Box<dyn std::error::Error + Send + Sync>
+                    })?;
+            let embedder: Box<dyn Embedder> = Box::new(rest_embedder);
+            embedders.insert(model_name, Arc::new(Mutex::new(embedder)));
+        }
+        _ => {
+            // Load every registered model once at cold start so any of them
+            // can be selected per request without recompiling.
+            for descriptor in MODEL_REGISTRY {
+                let onnx_embedder = OnnxEmbedder::new(descriptor).map_err(|e| {
+                    tracing::error!("Failed to initialize model `{}`: {}", descriptor.name, e);
+                    Box::new(e) as Box<dyn std::error::Error + Send + Sync>
+                })?;
+                let embedder: Box<dyn Embedder> = Box::new(onnx_embedder);
+                embedders.insert(descriptor.name.to_string(), Arc::new(Mutex::new(embedder)));
+            }
+        }
+    }
+
+    let embedders = Arc::new(embedders);
+
+    // Thread pool used to parallelize batch-embedding requests: tokenization
+    // for the ONNX backend, the per-text HTTP calls for the REST backend.
+    // Built once at cold start and shared across invocations.
+    let pool = rayon::ThreadPoolBuilder::new()
+        .build()
+        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
+    let pool = Arc::new(pool);
 
     // Start the Lambda runtime.
-    // Each incoming request will clone the Arc and call function_handler.
+    // Each incoming request will clone the Arcs and call function_handler.
     run(service_fn(move |event| {
-        let embedder = embedder.clone();
-        function_handler(embedder, event)
+        let embedders = embedders.clone();
+        let pool = pool.clone();
+        function_handler(embedders, pool, event)
     }))
     .await
 }